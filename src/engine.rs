@@ -0,0 +1,784 @@
+use crate::types::{Account, ClientID, Decimal, Transaction, TransactionID, TransactionType};
+use std::collections::HashMap;
+use std::fmt;
+
+// Structured failures the engine can report, so embedders can match on the
+// cause instead of parsing an error string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    InsufficientFunds { client_id: ClientID },
+    AccountLocked { client_id: ClientID },
+    MissingAmount { transaction_id: TransactionID },
+    DuplicateTransaction { transaction_id: TransactionID },
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::InsufficientFunds { client_id } => write!(
+                f,
+                "engine error: Client ID {} doesn't have sufficient avalable",
+                client_id
+            ),
+            EngineError::AccountLocked { client_id } => write!(
+                f,
+                "engine error: Client ID {} account is locked",
+                client_id
+            ),
+            EngineError::MissingAmount { transaction_id } => write!(
+                f,
+                "engine error: missing amount for transaction {}",
+                transaction_id
+            ),
+            EngineError::DuplicateTransaction { transaction_id } => write!(
+                f,
+                "engine error: transaction {} already exists",
+                transaction_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+// State of a deposit/withdrawal as it moves through the dispute workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxState {
+    Processed,
+    Disputed,
+    Resolved,
+    ChargedBack,
+}
+
+// A disputable transaction, looked up by (client, tx) on later dispute/resolve/chargeback rows.
+struct LedgerEntry {
+    amount: Decimal,
+    state: TxState,
+}
+
+#[derive(Default)]
+pub struct Engine {
+    client_account: HashMap<ClientID, Account>,
+    ledger: HashMap<(ClientID, TransactionID), LedgerEntry>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Processes a single transaction, updating client state incrementally.
+    pub fn process(&mut self, tr: Transaction) -> Result<(), EngineError> {
+        match tr.kind {
+            TransactionType::Deposit => {
+                let account = self
+                    .client_account
+                    .entry(tr.client_id)
+                    .or_insert_with(|| Account {
+                        client_id: tr.client_id,
+                        ..Default::default()
+                    });
+                if account.locked {
+                    return Err(EngineError::AccountLocked {
+                        client_id: account.client_id,
+                    });
+                }
+                if self.ledger.contains_key(&(tr.client_id, tr.transaction_id)) {
+                    return Err(EngineError::DuplicateTransaction {
+                        transaction_id: tr.transaction_id,
+                    });
+                }
+
+                let amount = tr.amount.ok_or(EngineError::MissingAmount {
+                    transaction_id: tr.transaction_id,
+                })?;
+                account.available += amount;
+                account.total += amount;
+                self.ledger.insert(
+                    (tr.client_id, tr.transaction_id),
+                    LedgerEntry {
+                        amount,
+                        state: TxState::Processed,
+                    },
+                );
+            }
+            TransactionType::Withdrawal => {
+                let account = self
+                    .client_account
+                    .entry(tr.client_id)
+                    .or_insert_with(|| Account {
+                        client_id: tr.client_id,
+                        ..Default::default()
+                    });
+                if account.locked {
+                    return Err(EngineError::AccountLocked {
+                        client_id: account.client_id,
+                    });
+                }
+                if self.ledger.contains_key(&(tr.client_id, tr.transaction_id)) {
+                    return Err(EngineError::DuplicateTransaction {
+                        transaction_id: tr.transaction_id,
+                    });
+                }
+
+                let amount = tr.amount.ok_or(EngineError::MissingAmount {
+                    transaction_id: tr.transaction_id,
+                })?;
+                if account.available < amount {
+                    return Err(EngineError::InsufficientFunds {
+                        client_id: account.client_id,
+                    });
+                }
+                account.available -= amount;
+                account.total -= amount;
+                self.ledger.insert(
+                    (tr.client_id, tr.transaction_id),
+                    LedgerEntry {
+                        amount,
+                        state: TxState::Processed,
+                    },
+                );
+            }
+            TransactionType::Dispute => {
+                let entry = match self.ledger.get_mut(&(tr.client_id, tr.transaction_id)) {
+                    Some(entry) if entry.state == TxState::Processed => entry,
+                    _ => return Ok(()),
+                };
+                let Some(account) = self.client_account.get_mut(&tr.client_id) else {
+                    return Ok(());
+                };
+
+                account.available -= entry.amount;
+                account.held += entry.amount;
+                entry.state = TxState::Disputed;
+            }
+            TransactionType::Resolve => {
+                let entry = match self.ledger.get_mut(&(tr.client_id, tr.transaction_id)) {
+                    Some(entry) if entry.state == TxState::Disputed => entry,
+                    _ => return Ok(()),
+                };
+                let Some(account) = self.client_account.get_mut(&tr.client_id) else {
+                    return Ok(());
+                };
+
+                account.available += entry.amount;
+                account.held -= entry.amount;
+                entry.state = TxState::Resolved;
+            }
+            TransactionType::Chargeback => {
+                let entry = match self.ledger.get_mut(&(tr.client_id, tr.transaction_id)) {
+                    Some(entry) if entry.state == TxState::Disputed => entry,
+                    _ => return Ok(()),
+                };
+                let Some(account) = self.client_account.get_mut(&tr.client_id) else {
+                    return Ok(());
+                };
+
+                account.held -= entry.amount;
+                account.total -= entry.amount;
+                entry.state = TxState::ChargedBack;
+                account.locked = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Stops at the first error, unlike a resilient caller that skips a bad
+    // row and keeps going (see main's transaction loop); use `process` directly
+    // if you need to continue past a rejected or malformed transaction.
+    pub fn process_all(&mut self, transactions: &[Transaction]) -> Result<(), EngineError> {
+        for tr in transactions {
+            self.process(tr.clone())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn accounts(&self) -> Vec<&Account> {
+        let mut accounts = self.client_account.values().collect::<Vec<_>>();
+        accounts.sort_by_key(|a| a.client_id);
+        accounts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deposit_increase_total_and_available() {
+        let t = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("1.0".parse().unwrap()),
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+        assert!(e.process_all(&[t]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "2.0".parse().unwrap());
+        assert_eq!(account.available, account.total);
+    }
+
+    #[test]
+    fn test_withdrawal_decrease_available_and_total() {
+        let t = Transaction {
+            kind: TransactionType::Withdrawal,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("5.0".parse().unwrap()),
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "10.0".parse().unwrap(),
+            available: "10.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+        assert!(e.process_all(&[t]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "5.0".parse().unwrap());
+        assert_eq!(account.available, account.total);
+    }
+
+    #[test]
+    fn test_withdrawal_with_not_sufficient_available() {
+        let t = Transaction {
+            kind: TransactionType::Withdrawal,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("5.0".parse().unwrap()),
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "3.0".parse().unwrap(),
+            available: "3.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+        assert_eq!(
+            e.process_all(&[t]),
+            Err(EngineError::InsufficientFunds { client_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_deposit_with_missing_amount_is_an_error() {
+        let t = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: None,
+        };
+
+        let mut e = Engine::default();
+        assert_eq!(
+            e.process_all(&[t]),
+            Err(EngineError::MissingAmount { transaction_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_withdrawal_with_missing_amount_is_an_error() {
+        let t = Transaction {
+            kind: TransactionType::Withdrawal,
+            client_id: 1,
+            transaction_id: 1,
+            amount: None,
+        };
+
+        let mut e = Engine::default();
+        assert_eq!(
+            e.process_all(&[t]),
+            Err(EngineError::MissingAmount { transaction_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_dispute_decrease_available_increase_held() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "1.0".parse().unwrap());
+        assert_eq!(account.held, "10.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_dispute_refere_to_not_existing_transaction() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 2,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_dispute_from_another_client_is_ignored() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 2,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "10.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_same_tx_id_from_different_clients_do_not_collide() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 2,
+            transaction_id: 1,
+            amount: Some("20.0".parse().unwrap()),
+        };
+        let t2 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        assert!(e.process_all(&[t0, t1, t2]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "0.0".parse().unwrap());
+        assert_eq!(account.held, "10.0".parse().unwrap());
+
+        let account = e.client_account.get(&2u16).unwrap();
+        assert_eq!(account.available, "20.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_replayed_deposit_does_not_clobber_an_existing_ledger_entry() {
+        let deposit = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("100.0".parse().unwrap()),
+        };
+        let dispute = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+        let chargeback = Transaction {
+            kind: TransactionType::Chargeback,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        assert!(e.process(deposit.clone()).is_ok());
+        assert!(e.process(dispute).is_ok());
+        assert_eq!(
+            e.process(deposit),
+            Err(EngineError::DuplicateTransaction { transaction_id: 1 })
+        );
+        assert!(e.process(chargeback).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "0.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+        assert_eq!(account.total, "0.0".parse().unwrap());
+        assert!(account.locked);
+    }
+
+    #[test]
+    fn test_dispute_for_unknown_client_does_not_fabricate_an_account() {
+        let t = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 99,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        assert!(e.process_all(&[t]).is_ok());
+        assert!(!e.client_account.contains_key(&99u16));
+    }
+
+    #[test]
+    fn test_double_dispute_is_ignored() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+        let t2 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        assert!(e.process_all(&[t0, t1, t2]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "0.0".parse().unwrap());
+        assert_eq!(account.held, "10.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_increase_available_decrease_held() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+        let t2 = Transaction {
+            kind: TransactionType::Resolve,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1, t2]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_refere_to_not_existing_transaction() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Resolve,
+            client_id: 1,
+            transaction_id: 11,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_refere_to_transaction_not_under_dispute() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Resolve,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_chargeback_decrease_total_decrease_held_and_lock() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Dispute,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+        let t2 = Transaction {
+            kind: TransactionType::Chargeback,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1, t2]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "1.0".parse().unwrap());
+        assert_eq!(account.total, "1.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+        assert!(account.locked);
+    }
+
+    #[test]
+    fn test_deposit_on_locked_account_is_rejected() {
+        let t = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+
+        let a = Account {
+            client_id: 1,
+            locked: true,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert_eq!(
+            e.process_all(&[t]),
+            Err(EngineError::AccountLocked { client_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_withdrawal_on_locked_account_is_rejected() {
+        let t = Transaction {
+            kind: TransactionType::Withdrawal,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("5.0".parse().unwrap()),
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "10.0".parse().unwrap(),
+            available: "10.0".parse().unwrap(),
+            locked: true,
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert_eq!(
+            e.process_all(&[t]),
+            Err(EngineError::AccountLocked { client_id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_chargeback_refere_to_not_existing_transaction() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Chargeback,
+            client_id: 1,
+            transaction_id: 11,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_chargeback_refere_to_transaction_not_under_dispute() {
+        let t0 = Transaction {
+            kind: TransactionType::Deposit,
+            client_id: 1,
+            transaction_id: 1,
+            amount: Some("10.0".parse().unwrap()),
+        };
+        let t1 = Transaction {
+            kind: TransactionType::Chargeback,
+            client_id: 1,
+            transaction_id: 1,
+            ..Default::default()
+        };
+
+        let a = Account {
+            client_id: 1,
+            total: "1.0".parse().unwrap(),
+            available: "1.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let mut e = Engine::default();
+        e.client_account.insert(a.client_id, a);
+
+        assert!(e.process_all(&[t0, t1]).is_ok());
+
+        let account = e.client_account.get(&1u16).unwrap();
+        assert_eq!(account.available, "11.0".parse().unwrap());
+        assert_eq!(account.total, "11.0".parse().unwrap());
+        assert_eq!(account.held, "0.0".parse().unwrap());
+    }
+}
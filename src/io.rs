@@ -0,0 +1,136 @@
+use crate::types::{Account, Transaction};
+use std::error::Error;
+use std::io::Write;
+
+// Opens a CSV reader that streams records one at a time, tolerant of trimmed/ragged rows.
+pub fn csv_reader(path: &str) -> Result<csv::Reader<std::fs::File>, Box<dyn Error>> {
+    Ok(csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(path)?)
+}
+
+pub fn read_csv(path: &str) -> Result<Vec<Transaction>, Box<dyn Error>> {
+    let mut reader = csv_reader(path)?;
+    let mut transactions = Vec::new();
+    for result in reader.deserialize() {
+        let trasaction: Transaction = result?;
+        transactions.push(trasaction);
+    }
+
+    Ok(transactions)
+}
+
+pub fn write_accounts(
+    accounts: &[&Account],
+    write_impl: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_writer(write_impl);
+    for a in accounts.iter() {
+        writer.serialize(a)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::Engine;
+
+    #[test]
+    fn test_read_csv_with_invalid_path() {
+        assert!(read_csv("ok").is_err());
+    }
+
+    #[test]
+    fn test_read_csv_with_not_csv_file() {
+        assert!(read_csv("Cargo.lock").is_err());
+    }
+
+    #[test]
+    fn test_read_csv_ok() {
+        let ret = read_csv("testdata/transactions.csv");
+        assert!(ret.is_ok());
+        assert!(ret.unwrap().len() == 2);
+    }
+
+    #[test]
+    fn test_read_csv_ok_with_four_decimal() {
+        let ret = read_csv("testdata/transactions.csv");
+        let tr = ret.unwrap();
+        assert_eq!(tr[0].amount, Some("1.0191".parse().unwrap()));
+        assert_eq!(tr[1].amount, Some("2.0001".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_serialize_account_ok() {
+        let a = Account {
+            client_id: 1,
+            available: "1.5".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            total: "1.5".parse().unwrap(),
+            locked: false,
+        };
+
+        let b = Account {
+            client_id: 2,
+            available: "2.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            total: "2.0".parse().unwrap(),
+            locked: false,
+        };
+
+        let accounts = vec![&a, &b];
+
+        let mut output: Vec<u8> = Vec::new();
+        let ret = write_accounts(&accounts, &mut output);
+        assert!(ret.is_ok());
+        let data = String::from_utf8(output);
+        assert!(data.is_ok());
+        let expected = std::fs::read_to_string("testdata/accounts.csv");
+        assert!(expected.is_ok());
+        assert_eq!(data.unwrap(), expected.unwrap());
+    }
+
+    #[test]
+    fn test_serialize_output_four_decimal_precision() {
+        let account = Account {
+            client_id: 2,
+            available: "2.0".parse().unwrap(),
+            held: "0.0".parse().unwrap(),
+            total: "2.0".parse().unwrap(),
+            locked: false,
+        };
+        let accounts = vec![&account];
+
+        let mut output: Vec<u8> = Vec::new();
+        write_accounts(&accounts, &mut output).unwrap();
+        let data = String::from_utf8(output).unwrap();
+        assert!(data.contains("2.0000"));
+    }
+
+    #[test]
+    fn test_only_deposit() {
+        let transactions = read_csv("testdata/transactions.csv").unwrap();
+        let mut engine = Engine::new();
+        let ret = engine.process_all(&transactions);
+        assert!(ret.is_ok());
+        let mut output: Vec<u8> = Vec::new();
+        let ret = write_accounts(&engine.accounts(), &mut output);
+        assert!(ret.is_ok());
+
+        let data = String::from_utf8(output).unwrap();
+        assert_eq!(
+            String::from(
+                "client,available,held,total,locked
+1,1.0191,0.0000,1.0191,false
+2,2.0001,0.0000,2.0001,false
+"
+            ),
+            data
+        )
+    }
+}
@@ -0,0 +1,6 @@
+pub mod engine;
+pub mod io;
+pub mod types;
+
+pub use engine::{Engine, EngineError};
+pub use types::{Account, ClientID, Transaction, TransactionID, TransactionType};
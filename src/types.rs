@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+pub type TransactionID = u32;
+pub type ClientID = u16;
+
+// Fixed-point decimal with 4 fractional digits, stored as ten-thousandths of a unit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal(i64);
+
+impl Decimal {
+    const SCALE: i64 = 10_000;
+}
+
+impl std::ops::Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Decimal(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Decimal(self.0 - rhs.0)
+    }
+}
+
+impl std::ops::AddAssign for Decimal {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl std::ops::SubAssign for Decimal {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl std::str::FromStr for Decimal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let negative = s.starts_with('-');
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+        if unsigned.starts_with('-') {
+            return Err(format!("'{}' is not a valid decimal amount", s));
+        }
+
+        let mut parts = unsigned.splitn(2, '.');
+        let units = parts.next().unwrap_or("0");
+        let fraction = parts.next().unwrap_or("");
+        if fraction.len() > 4 {
+            return Err(format!(
+                "'{}' has more than 4 fractional digits",
+                s
+            ));
+        }
+
+        let units: i64 = units
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid decimal amount", s))?;
+        let fraction: i64 = format!("{:0<4}", fraction)
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid decimal amount", s))?;
+
+        let magnitude = units
+            .checked_mul(Decimal::SCALE)
+            .and_then(|u| u.checked_add(fraction))
+            .ok_or_else(|| format!("'{}' is out of range for a Decimal", s))?;
+        let magnitude = if negative {
+            magnitude
+                .checked_neg()
+                .ok_or_else(|| format!("'{}' is out of range for a Decimal", s))?
+        } else {
+            magnitude
+        };
+        Ok(Decimal(magnitude))
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let magnitude = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:04}",
+            sign,
+            magnitude / Decimal::SCALE as u64,
+            magnitude % Decimal::SCALE as u64
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+// Deserialized via `try_from` so an unrecognized value in the CSV `type`
+// column becomes a clean deserialize error instead of a panic deep inside
+// the engine.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum TransactionType {
+    #[default]
+    Deposit,
+    Withdrawal,
+    Dispute,
+    Resolve,
+    Chargeback,
+}
+
+impl std::str::FromStr for TransactionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deposit" => Ok(TransactionType::Deposit),
+            "withdrawal" => Ok(TransactionType::Withdrawal),
+            "dispute" => Ok(TransactionType::Dispute),
+            "resolve" => Ok(TransactionType::Resolve),
+            "chargeback" => Ok(TransactionType::Chargeback),
+            _ => Err(format!("'{}' is not a valid value for TransactionType", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TransactionType {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(Default, Clone, Deserialize)]
+pub struct Transaction {
+    // Type of transaction.
+    #[serde(alias = "type")]
+    pub kind: TransactionType,
+
+    // Client ID.
+    #[serde(alias = "client")]
+    pub client_id: ClientID,
+
+    // Transaction ID.
+    #[serde(alias = "tx")]
+    pub transaction_id: TransactionID,
+
+    // Only deposits and withdrawals carry an amount; dispute/resolve/chargeback
+    // rows legitimately omit this column.
+    pub amount: Option<Decimal>,
+}
+
+#[derive(Default, Serialize)]
+pub struct Account {
+    // Client ID.
+    #[serde(rename = "client")]
+    pub client_id: ClientID,
+
+    // Total founds available for trading.
+    // Should be equal to (total - held).
+    pub available: Decimal,
+
+    // Total founds held for dispute.
+    // Should be equal to (total - available).
+    pub held: Decimal,
+
+    // The total funds that are available or held.
+    // This should be equal to (available + held).
+    pub total: Decimal,
+
+    // Set on chargeback; blocks deposit/withdrawal but not dispute/resolve/chargeback on existing txs.
+    pub locked: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decimal_rejects_more_than_four_fractional_digits() {
+        assert!("1.00001".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_decimal_roundtrips_through_display() {
+        let d: Decimal = "1.5".parse().unwrap();
+        assert_eq!(d.to_string(), "1.5000");
+        assert_eq!(d.to_string().parse::<Decimal>().unwrap(), d);
+    }
+
+    #[test]
+    fn test_decimal_rejects_doubled_leading_minus() {
+        assert!("--1.0".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_decimal_rejects_magnitude_overflow() {
+        assert!("922337203685478.5807".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_unrecognized_kind_is_a_deserialize_error() {
+        assert!(TransactionType::try_from("refund".to_string()).is_err());
+    }
+}